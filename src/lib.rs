@@ -0,0 +1,662 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use goblin::pe::PE;
+use pdb::FallibleIterator;
+use indicatif::{ProgressBar, ProgressStyle};
+use reqwest::{Client, StatusCode};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+
+/// Errors produced while parsing a DLL or fetching its PDB from a symbol
+/// server.
+#[derive(Error, Debug)]
+pub enum SymbolError {
+    /// The input DLL cannot be read, is not a PE image, or carries no PDB70
+    /// debug record; also returned when a PDB cannot be opened for symbolizing.
+    #[error("parse error: {0}")]
+    ParseError(String),
+
+    /// An HTTP request to the symbol server failed at the transport level.
+    #[error("http error: {0}")]
+    HttpError(#[from] reqwest::Error),
+
+    /// The symbol server does not have the requested PDB.
+    #[error("symbol not found: {0}")]
+    NotFound(String),
+
+    /// The downloaded artifact could not be written, renamed, read or
+    /// extracted, or the recovered PDB failed its integrity check.
+    #[error("cab error: {0}")]
+    CabError(String),
+}
+
+/// Convenience alias for results produced by this crate.
+pub type Result<T> = std::result::Result<T, SymbolError>;
+
+/// The identifying information extracted from a DLL's PDB70 debug record,
+/// together with the paths the PDB and cabinet are written to.
+#[derive(Debug, Clone)]
+pub struct DllInfo {
+    pub dll_path: String,
+    pub dll_guid: String,
+    pub age: u32,
+    pub pdb_name: String,
+    pub pdb_path: String,
+    pub cab_path: String,
+}
+
+impl DllInfo {
+    /// The symbol-store index key for this PDB: the GUID followed by the PDB
+    /// age as upper-case hex with no padding, e.g. `{GUID}1` or `{GUID}A`.
+    pub fn index_key(&self) -> String {
+        format!("{}{:X}", self.dll_guid, self.age)
+    }
+
+    /// The PDB file name including its extension, e.g. `mono-2.0-bdwgc.pdb`.
+    pub fn pdb_file_name(&self) -> String {
+        format!("{}.pdb", self.pdb_name)
+    }
+}
+
+/// Format a 16-byte PDB70 signature as the upper-case GUID string symbol
+/// servers key PDBs by (the mixed-endian `{Data1}{Data2}{Data3}{Data4..}`
+/// layout, with no separators).
+fn format_guid(guid: &[u8; 16]) -> String {
+    format!(
+        "{:08X}{:04X}{:04X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+        u32::from_le_bytes([guid[0], guid[1], guid[2], guid[3]]),
+        u16::from_le_bytes([guid[4], guid[5]]),
+        u16::from_le_bytes([guid[6], guid[7]]),
+        guid[8], guid[9], guid[10], guid[11], guid[12], guid[13], guid[14], guid[15]
+    )
+}
+
+/// Render a parsed PDB `Uuid` into the same separator-less, mixed-endian
+/// upper-case key form [`format_guid`] produces from a DLL's raw signature, so
+/// the two renderings can be compared directly.
+fn format_uuid(guid: &uuid::Uuid) -> String {
+    let (d1, d2, d3, d4) = guid.as_fields();
+    format!(
+        "{:08X}{:04X}{:04X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+        d1, d2, d3, d4[0], d4[1], d4[2], d4[3], d4[4], d4[5], d4[6], d4[7]
+    )
+}
+
+/// Parse a DLL and extract the information needed to locate its PDB on a
+/// symbol server.
+pub fn parse_dll(dll_path: &str) -> Result<DllInfo> {
+    let mut dll_buffer = Vec::new();
+    File::open(dll_path)
+        .and_then(|mut f| f.read_to_end(&mut dll_buffer))
+        .map_err(|e| SymbolError::ParseError(format!("cannot read {dll_path}: {e}")))?;
+
+    let pe = PE::parse(&dll_buffer).map_err(|e| SymbolError::ParseError(e.to_string()))?;
+    let debug_info = pe
+        .debug_data
+        .ok_or_else(|| SymbolError::ParseError("dll has no debug data".to_string()))?
+        .codeview_pdb70_debug_info
+        .ok_or_else(|| SymbolError::ParseError("dll has no PDB70 debug info".to_string()))?;
+
+    let pdb_path_raw = std::str::from_utf8(debug_info.filename)
+        .unwrap_or("")
+        .trim_end_matches('\0')
+        .trim_end();
+
+    let pdb_name = Path::new(pdb_path_raw)
+        .file_name()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| SymbolError::ParseError("cannot parse pdb name".to_string()))?
+        .to_string();
+
+    let pdb_name_without_ext = Path::new(pdb_path_raw)
+        .file_stem()
+        .and_then(|n| n.to_str())
+        .ok_or_else(|| SymbolError::ParseError("cannot parse pdb name".to_string()))?
+        .to_string();
+
+    let dll_guid = format_guid(&debug_info.signature);
+
+    let parent = Path::new(dll_path).parent().unwrap_or_else(|| Path::new("."));
+    let cab_path = parent
+        .join(format!("{}.cab", pdb_name_without_ext))
+        .to_string_lossy()
+        .to_string();
+    let pdb_path = parent.join(&pdb_name).to_string_lossy().to_string();
+
+    Ok(DllInfo {
+        dll_path: dll_path.to_string(),
+        dll_guid,
+        age: debug_info.age,
+        pdb_name: pdb_name_without_ext,
+        pdb_path,
+        cab_path,
+    })
+}
+
+/// An async symbol fetcher that embeds the `reqwest::Client`, the directory
+/// downloaded PDBs are cached in, and the symbol servers to query.
+///
+/// Other Rust tools can embed this to get Unity PDB fetching without the CLI.
+pub struct SymbolDownloader {
+    client: Client,
+    cache_dir: Option<PathBuf>,
+    servers: Vec<String>,
+}
+
+/// Unity's public symbol server.
+pub const UNITY_SYMBOL_SERVER: &str = "http://symbolserver.unity3d.com";
+
+/// Microsoft's public symbol server.
+pub const MICROSOFT_SYMBOL_SERVER: &str = "https://msdl.microsoft.com/download/symbols";
+
+/// Whether a downloaded artifact still needs to be extracted from a cabinet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FetchKind {
+    /// A compressed `*.pd_` cabinet was written to `cab_path`.
+    Compressed,
+    /// An uncompressed `*.pdb` was written straight to `pdb_path`.
+    Uncompressed,
+}
+
+impl SymbolDownloader {
+    /// Build a downloader that queries the Unity and Microsoft symbol servers,
+    /// in that order. When `cache_dir` is `Some`, extracted PDBs are laid out
+    /// in the canonical two-tier symbol store and re-used across runs.
+    pub fn new(cache_dir: Option<PathBuf>) -> Result<Self> {
+        Self::with_servers(
+            cache_dir,
+            vec![
+                UNITY_SYMBOL_SERVER.to_string(),
+                MICROSOFT_SYMBOL_SERVER.to_string(),
+            ],
+        )
+    }
+
+    /// Build a downloader with an explicit, ordered list of symbol servers.
+    pub fn with_servers(cache_dir: Option<PathBuf>, servers: Vec<String>) -> Result<Self> {
+        let client = Client::builder().build()?;
+        Ok(Self {
+            client,
+            cache_dir,
+            servers,
+        })
+    }
+
+    /// The symbol store directory extracted PDBs are cached in, if any.
+    pub fn cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+    /// Resolve the output paths for `dll_info`. With a symbol cache configured,
+    /// the PDB lands at `DIR/name.pdb/{GUID}{age}/name.pdb` and the cabinet
+    /// alongside it; otherwise the sibling-of-DLL paths from [`parse_dll`] are
+    /// kept.
+    ///
+    /// Exposed so callers can find where [`fetch_pdb`](Self::fetch_pdb) wrote a
+    /// PDB — for instance to feed it to a [`Symbolizer`].
+    pub fn resolved(&self, dll_info: &DllInfo) -> DllInfo {
+        let Some(cache_dir) = &self.cache_dir else {
+            return dll_info.clone();
+        };
+        let store = cache_dir.join(dll_info.pdb_file_name()).join(dll_info.index_key());
+        DllInfo {
+            pdb_path: store.join(dll_info.pdb_file_name()).to_string_lossy().to_string(),
+            cab_path: store.join(format!("{}.pd_", dll_info.pdb_name)).to_string_lossy().to_string(),
+            ..dll_info.clone()
+        }
+    }
+
+    /// Locate the PDB for `dll_info` by trying each configured server in turn,
+    /// returning the resolved URL plus whether it is a compressed cabinet.
+    ///
+    /// For every server the compressed (`name.pdb/KEY/name.pd_`) path is tried
+    /// first, then the uncompressed (`name.pdb/KEY/name.pdb`) path, then a
+    /// `file.ptr` pointer file that redirects to another URL. The first server
+    /// that answers with `200` wins. Existence is checked with `HEAD` so the
+    /// body is fetched only by the resumable transfer that follows.
+    async fn locate(&self, dll_info: &DllInfo) -> Result<(String, FetchKind)> {
+        let key = dll_info.index_key();
+        let pdb_file = dll_info.pdb_file_name();
+
+        for server in &self.servers {
+            let base = format!("{}/{}/{}", server.trim_end_matches('/'), pdb_file, key);
+
+            // Compressed cabinet.
+            let compressed = format!("{}/{}.pd_", base, dll_info.pdb_name);
+            if self.head_ok(&compressed).await {
+                return Ok((compressed, FetchKind::Compressed));
+            }
+
+            // Uncompressed PDB.
+            let uncompressed = format!("{}/{}", base, pdb_file);
+            if self.head_ok(&uncompressed).await {
+                return Ok((uncompressed, FetchKind::Uncompressed));
+            }
+
+            // Pointer file redirecting elsewhere.
+            let ptr = format!("{}/file.ptr", base);
+            if let Ok(resp) = self.client.get(&ptr).send().await {
+                if resp.status().is_success() {
+                    if let Some(target) = parse_file_ptr(&resp.text().await?) {
+                        if self.head_ok(&target).await {
+                            let kind = if target.ends_with(".pd_") {
+                                FetchKind::Compressed
+                            } else {
+                                FetchKind::Uncompressed
+                            };
+                            return Ok((target, kind));
+                        }
+                    }
+                }
+            }
+        }
+
+        Err(SymbolError::NotFound(format!(
+            "{} not found on any of {} server(s)",
+            pdb_file,
+            self.servers.len()
+        )))
+    }
+
+    /// Return `true` only if a `HEAD` request answers with a success status;
+    /// transport errors and non-success statuses fall through to the next
+    /// candidate.
+    async fn head_ok(&self, url: &str) -> bool {
+        matches!(self.client.head(url).send().await, Ok(resp) if resp.status().is_success())
+    }
+
+    /// Download `url` into `dest`, resuming from a `dest.part` sidecar when one
+    /// is present.
+    ///
+    /// A `Range: bytes=<len>-` header is sent for the already-downloaded prefix;
+    /// on `206 Partial Content` the body is appended, and on `200 OK` (a server
+    /// that ignored the range, or a first attempt) the download restarts from
+    /// scratch. The completed `.part` is renamed into place only once the whole
+    /// transfer succeeds, so an interrupted run leaves a resumable remnant
+    /// rather than a truncated final file.
+    async fn download_resumable(
+        &self,
+        url: &str,
+        dest: &str,
+        dll_info: &DllInfo,
+        progress_bar: &ProgressBar,
+    ) -> Result<()> {
+        let part = format!("{dest}.part");
+        let resumed = tokio::fs::metadata(&part).await.map(|m| m.len()).unwrap_or(0);
+
+        let mut request = self.client.get(url);
+        if resumed > 0 {
+            request = request.header(reqwest::header::RANGE, format!("bytes={resumed}-"));
+        }
+        let resp = request.send().await?;
+        if !resp.status().is_success() {
+            return Err(SymbolError::NotFound(format!("{} returned {}", dll_info.pdb_name, resp.status())));
+        }
+
+        let content_len = resp.content_length().unwrap_or(0);
+        let (mut out_file, downloaded) = if resp.status() == StatusCode::PARTIAL_CONTENT && resumed > 0 {
+            let f = tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(&part)
+                .await
+                .map_err(|e| SymbolError::CabError(e.to_string()))?;
+            (f, resumed)
+        } else {
+            // 200 OK: the server served the whole file, so start a fresh `.part`.
+            let f = tokio::fs::File::create(&part)
+                .await
+                .map_err(|e| SymbolError::CabError(e.to_string()))?;
+            (f, 0)
+        };
+
+        progress_bar.set_length(downloaded + content_len);
+        progress_bar.set_position(downloaded);
+        progress_bar.set_message(format!("{} download", dll_info.pdb_name));
+        progress_bar.set_style(
+            ProgressStyle::default_bar()
+                .template("{spinner:.green} {msg} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
+                .unwrap(),
+        );
+
+        let mut stream = resp.bytes_stream();
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk?;
+            out_file.write_all(&chunk).await.map_err(|e| SymbolError::CabError(e.to_string()))?;
+            progress_bar.inc(chunk.len() as u64);
+        }
+        out_file.flush().await.map_err(|e| SymbolError::CabError(e.to_string()))?;
+        drop(out_file);
+
+        tokio::fs::rename(&part, dest)
+            .await
+            .map_err(|e| SymbolError::CabError(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Extract the cabinet at `cab_path` into `pdb_path`.
+    async fn extract_cab(&self, cab_path: &str, pdb_path: &str, progress_bar: &ProgressBar) -> Result<()> {
+        progress_bar.set_message("extract");
+        progress_bar.set_style(
+            ProgressStyle::default_spinner()
+                .template("{spinner:.green} {msg} {elapsed_precise}")
+                .unwrap(),
+        );
+        progress_bar.enable_steady_tick(Duration::from_millis(100));
+
+        let cab_path = cab_path.to_string();
+        let pdb_path = pdb_path.to_string();
+        let result = tokio::task::spawn_blocking(move || -> Result<()> {
+            let cab_file = File::open(&cab_path).map_err(|e| SymbolError::CabError(e.to_string()))?;
+            let mut cabinet = cab::Cabinet::new(cab_file).map_err(|e| SymbolError::CabError(e.to_string()))?;
+
+            let mut files: Vec<String> = vec![];
+            for folder in cabinet.folder_entries() {
+                for file in folder.file_entries() {
+                    files.push(file.name().to_string());
+                }
+            }
+
+            for file in files {
+                let mut reader = cabinet.read_file(file.as_str()).map_err(|e| SymbolError::CabError(e.to_string()))?;
+                let mut writer = File::create(&pdb_path).map_err(|e| SymbolError::CabError(e.to_string()))?;
+                std::io::copy(&mut reader, &mut writer).map_err(|e| SymbolError::CabError(e.to_string()))?;
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| SymbolError::CabError(e.to_string()))?;
+
+        progress_bar.disable_steady_tick();
+        result
+    }
+
+    /// Re-read the recovered PDB's own PDB70 signature and age and confirm they
+    /// match the `DllInfo` the download was keyed on, so a truncated or
+    /// wrong-symbol file is never silently accepted.
+    fn verify_pdb(dll_info: &DllInfo) -> Result<()> {
+        let file = File::open(&dll_info.pdb_path).map_err(|e| SymbolError::CabError(e.to_string()))?;
+        let mut pdb = pdb::PDB::open(file).map_err(|e| SymbolError::CabError(e.to_string()))?;
+        let info = pdb.pdb_information().map_err(|e| SymbolError::CabError(e.to_string()))?;
+
+        // Render the PDB's `Uuid` into the same mixed-endian symbol-store key
+        // form `format_guid` produces from the DLL, so both sides are compared
+        // byte-for-byte rather than in two different orderings.
+        let guid = format_uuid(&info.guid);
+        if guid != dll_info.dll_guid || info.age != dll_info.age {
+            return Err(SymbolError::CabError(format!(
+                "integrity check failed for {}: PDB is {}/{} but DLL expects {}/{}",
+                dll_info.pdb_name, guid, info.age, dll_info.dll_guid, dll_info.age
+            )));
+        }
+        Ok(())
+    }
+
+    /// Locate and download the symbol artifact for `dll_info`, resuming a
+    /// partial transfer when one is present. A compressed cabinet lands at
+    /// `cab_path` and still needs [`extract`](Self::extract); an uncompressed
+    /// PDB is written straight to `pdb_path`. The [`FetchKind`] of the artifact
+    /// that was fetched is returned so callers can decide whether to extract.
+    pub async fn download(&self, dll_info: &DllInfo, progress_bar: &ProgressBar) -> Result<FetchKind> {
+        let info = self.resolved(dll_info);
+
+        if let Some(parent) = Path::new(&info.pdb_path).parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| SymbolError::CabError(e.to_string()))?;
+        }
+
+        let (url, kind) = self.locate(&info).await?;
+        let dest = match kind {
+            FetchKind::Compressed => &info.cab_path,
+            FetchKind::Uncompressed => &info.pdb_path,
+        };
+        self.download_resumable(&url, dest, &info, progress_bar).await?;
+        Ok(kind)
+    }
+
+    /// Extract the downloaded cabinet for `dll_info` into its PDB path and
+    /// remove the spent `.pd_` artifact.
+    pub async fn extract(&self, dll_info: &DllInfo, progress_bar: &ProgressBar) -> Result<()> {
+        let info = self.resolved(dll_info);
+        self.extract_cab(&info.cab_path, &info.pdb_path, progress_bar).await?;
+        tokio::fs::remove_file(&info.cab_path).await.ok();
+        Ok(())
+    }
+
+    /// Fetch and verify the PDB for `dll_info` in one call, resuming a partial
+    /// download when one is present and confirming the recovered PDB's identity
+    /// before returning. An uncompressed PDB is served straight to `pdb_path`.
+    ///
+    /// When a symbol cache is configured, the canonical store path is checked
+    /// first and the network round-trip is skipped entirely if the PDB is
+    /// already present.
+    pub async fn fetch_pdb(&self, dll_info: &DllInfo, progress_bar: &ProgressBar) -> Result<()> {
+        let info = self.resolved(dll_info);
+
+        if Path::new(&info.pdb_path).exists() {
+            progress_bar.set_message(format!("{} cached", info.pdb_name));
+            return Ok(());
+        }
+
+        if let Some(parent) = Path::new(&info.pdb_path).parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| SymbolError::CabError(e.to_string()))?;
+        }
+
+        let (url, kind) = self.locate(&info).await?;
+        match kind {
+            FetchKind::Uncompressed => {
+                self.download_resumable(&url, &info.pdb_path, &info, progress_bar).await?;
+            }
+            FetchKind::Compressed => {
+                // Always land the cabinet on disk so an interrupted transfer
+                // leaves a `.part` the next run can resume with a Range request,
+                // then extract it and drop the spent cabinet.
+                self.download_resumable(&url, &info.cab_path, &info, progress_bar).await?;
+                self.extract_cab(&info.cab_path, &info.pdb_path, progress_bar).await?;
+                tokio::fs::remove_file(&info.cab_path).await.ok();
+            }
+        }
+
+        Self::verify_pdb(&info)?;
+        Ok(())
+    }
+}
+
+/// An RVA→symbol index built from a downloaded PDB, turning raw addresses into
+/// the `module!symbol+0xdelta` lines a debugger or `symbolizer-rs` would print.
+///
+/// Public symbols (`S_PUB32`) from the global stream and procedure records
+/// (`S_GPROC32`/`S_LPROC32`) from each module's DBI symbol stream are mapped to
+/// their relative virtual addresses and kept sorted, so a lookup is a binary
+/// search for the nearest symbol at or below the queried address.
+pub struct Symbolizer {
+    module: String,
+    symbols: Vec<(u32, String)>,
+}
+
+impl Symbolizer {
+    /// Build a symbolizer from the PDB at `pdb_path`, labelling resolved lines
+    /// with `module` (typically the DLL's base name). Public symbols from the
+    /// global stream and procedure symbols from every module's DBI stream are
+    /// indexed by their RVA.
+    pub fn open(pdb_path: &str, module: &str) -> Result<Self> {
+        let file = File::open(pdb_path).map_err(|e| SymbolError::ParseError(e.to_string()))?;
+        let mut pdb = pdb::PDB::open(file).map_err(|e| SymbolError::ParseError(e.to_string()))?;
+        let address_map = pdb
+            .address_map()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?;
+
+        let mut symbols = Vec::new();
+
+        // Public symbols (`S_PUB32`) live in the global stream.
+        let symbol_table = pdb
+            .global_symbols()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?;
+        let mut iter = symbol_table.iter();
+        while let Some(symbol) = iter
+            .next()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?
+        {
+            if let Ok(pdb::SymbolData::Public(data)) = symbol.parse() {
+                if let Some(rva) = data.offset.to_rva(&address_map) {
+                    symbols.push((rva.0, data.name.to_string().into_owned()));
+                }
+            }
+        }
+
+        // Procedure symbols (`S_GPROC32`/`S_LPROC32`) live per-module in the
+        // DBI streams, so walk each module's own symbol stream for them.
+        let debug_info = pdb
+            .debug_information()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?;
+        let mut modules = debug_info
+            .modules()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?;
+        while let Some(module) = modules
+            .next()
+            .map_err(|e| SymbolError::ParseError(e.to_string()))?
+        {
+            let Some(module_info) = pdb
+                .module_info(&module)
+                .map_err(|e| SymbolError::ParseError(e.to_string()))?
+            else {
+                continue;
+            };
+            let mut module_symbols = module_info
+                .symbols()
+                .map_err(|e| SymbolError::ParseError(e.to_string()))?;
+            while let Some(symbol) = module_symbols
+                .next()
+                .map_err(|e| SymbolError::ParseError(e.to_string()))?
+            {
+                if let Ok(pdb::SymbolData::Procedure(data)) = symbol.parse() {
+                    if let Some(rva) = data.offset.to_rva(&address_map) {
+                        symbols.push((rva.0, data.name.to_string().into_owned()));
+                    }
+                }
+            }
+        }
+
+        symbols.sort_by_key(|(rva, _)| *rva);
+        symbols.dedup_by_key(|(rva, _)| *rva);
+
+        Ok(Self {
+            module: module.to_string(),
+            symbols,
+        })
+    }
+
+    /// Resolve `rva` to `module!symbol+0xdelta`, or `module+0xrva` when no
+    /// indexed symbol covers the address. When `demangle` is set, MSVC-decorated
+    /// names are run through `msvc-demangler` for a readable signature.
+    pub fn resolve(&self, rva: u32, demangle: bool) -> String {
+        match self.symbols.binary_search_by(|(sym_rva, _)| sym_rva.cmp(&rva)) {
+            Ok(idx) => self.format(idx, rva, demangle),
+            Err(0) => format!("{}+{:#x}", self.module, rva),
+            Err(idx) => self.format(idx - 1, rva, demangle),
+        }
+    }
+
+    /// Render the symbol at `idx` relative to the queried `rva`.
+    fn format(&self, idx: usize, rva: u32, demangle: bool) -> String {
+        let (sym_rva, name) = &self.symbols[idx];
+        let name = if demangle { demangle_name(name) } else { name.clone() };
+        let delta = rva - sym_rva;
+        if delta == 0 {
+            format!("{}!{}", self.module, name)
+        } else {
+            format!("{}!{}+{:#x}", self.module, name, delta)
+        }
+    }
+}
+
+/// Demangle an MSVC-decorated symbol name, falling back to the raw name when it
+/// is already undecorated or cannot be parsed.
+fn demangle_name(name: &str) -> String {
+    msvc_demangler::demangle(name, msvc_demangler::DemangleFlags::llvm())
+        .unwrap_or_else(|_| name.to_string())
+}
+
+/// Parse a SymSrv `file.ptr` pointer file, returning the URL or path it
+/// redirects to. The payload is either a bare path/URL or a `PATH:<target>`
+/// line; a `MSG:` diagnostic line yields `None`.
+fn parse_file_ptr(body: &str) -> Option<String> {
+    let line = body.lines().next()?.trim();
+    if let Some(rest) = line.strip_prefix("PATH:") {
+        Some(rest.trim().to_string())
+    } else if line.is_empty() || line.starts_with("MSG:") {
+        None
+    } else {
+        Some(line.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guid_renderings_agree() {
+        // A real PDB70 signature, stored little-endian for Data1/2/3 on the
+        // wire, must key to the same symbol-store string whether read from the
+        // DLL's raw bytes or from the `Uuid` the `pdb` crate parses out of the
+        // PDB stream.
+        let signature: [u8; 16] = [
+            0x2f, 0x3c, 0x08, 0x4d, 0x7a, 0x1b, 0x9e, 0x42, 0xa0, 0x5c, 0x11, 0x22, 0x33, 0x44,
+            0x55, 0x66,
+        ];
+        let dll_key = format_guid(&signature);
+
+        let d4: [u8; 8] = signature[8..16].try_into().unwrap();
+        let parsed = uuid::Uuid::from_fields(
+            u32::from_le_bytes([signature[0], signature[1], signature[2], signature[3]]),
+            u16::from_le_bytes([signature[4], signature[5]]),
+            u16::from_le_bytes([signature[6], signature[7]]),
+            &d4,
+        );
+
+        assert_eq!(dll_key, "4D083C2F1B7A429EA05C112233445566");
+        assert_eq!(dll_key, format_uuid(&parsed));
+    }
+
+    #[test]
+    fn resolve_covers_search_boundaries() {
+        let symbolizer = Symbolizer {
+            module: "game".to_string(),
+            symbols: vec![(0x1000, "foo".to_string()), (0x2000, "bar".to_string())],
+        };
+
+        // Below every symbol: fall back to module+offset.
+        assert_eq!(symbolizer.resolve(0x0500, false), "game+0x500");
+        // Exact hit: no delta suffix.
+        assert_eq!(symbolizer.resolve(0x1000, false), "game!foo");
+        // Between two symbols: nearest one below, plus the delta.
+        assert_eq!(symbolizer.resolve(0x1800, false), "game!foo+0x800");
+        // At and past the last symbol.
+        assert_eq!(symbolizer.resolve(0x2000, false), "game!bar");
+        assert_eq!(symbolizer.resolve(0x2400, false), "game!bar+0x400");
+    }
+
+    #[test]
+    fn parse_file_ptr_variants() {
+        // `PATH:` prefix redirects to the trimmed target.
+        assert_eq!(
+            parse_file_ptr("PATH:http://host/a.pdb"),
+            Some("http://host/a.pdb".to_string())
+        );
+        // A bare line is itself the target.
+        assert_eq!(
+            parse_file_ptr("http://host/bare.pdb"),
+            Some("http://host/bare.pdb".to_string())
+        );
+        // Diagnostic and empty payloads resolve to nothing.
+        assert_eq!(parse_file_ptr("MSG:not indexed"), None);
+        assert_eq!(parse_file_ptr(""), None);
+    }
+}
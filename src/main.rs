@@ -1,179 +1,260 @@
 use std::error::Error;
-use std::fs::File;
-use std::io::{Error as IOError, ErrorKind, Read};
+use std::io::Read;
 use std::path::Path;
-use std::time::Duration;
-
-use clap::{arg, command, Parser};
-use futures_util::StreamExt;
-use goblin::pe::PE;
-use indicatif::{ProgressBar, ProgressStyle};
-use reqwest::Client;
-use tokio::io::AsyncWriteExt;
-
-#[derive(Debug)]
-struct DllInfo {
-    dll_path: String,
-    dll_guid: String,
-    pdb_name: String,
-    pdb_path: String,
-    cab_path: String,
-}
-
-fn parse_dll(dll_path: &str) -> Result<DllInfo, Box<dyn Error>> {
-    let mut dll_buffer = Vec::new();
-    File::open(dll_path)?.read_to_end(&mut dll_buffer)?;
-
-    let pe = PE::parse(&dll_buffer).expect("dll parse failed");
-    let debug_info = pe.debug_data.expect("dll no debug data")
-        .codeview_pdb70_debug_info.expect("dll no debug info");
-
-    let pdb_path_raw = std::str::from_utf8(debug_info.filename)
-        .unwrap_or("")
-        .trim_end_matches('\0')
-        .trim_end();
-
-    let pdb_name = Path::new(pdb_path_raw)
-        .file_name().expect("parse pdb name failed")
-        .to_str().expect("parse pdb name failed")
-        .to_string();
-
-    let pdb_name_without_ext = Path::new(pdb_path_raw)
-        .file_stem().expect("parse pdb name failed")
-        .to_str().expect("parse pdb name failed")
-        .to_string();
-
-    let guid_buf = debug_info.signature;
-    let dll_guid = format!(
-        "{:08X}{:04X}{:04X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
-        u32::from_le_bytes([guid_buf[0], guid_buf[1], guid_buf[2], guid_buf[3]]),
-        u16::from_le_bytes([guid_buf[4], guid_buf[5]]),
-        u16::from_le_bytes([guid_buf[6], guid_buf[7]]),
-        guid_buf[8], guid_buf[9], guid_buf[10], guid_buf[11], guid_buf[12], guid_buf[13], guid_buf[14], guid_buf[15]
-    );
 
-    let cab_path = Path::new(dll_path).parent().unwrap();
-    let cab_path = Path::join(&cab_path, format!("{}.cab", pdb_name_without_ext));
-    let cab_path = cab_path.to_str().unwrap();
+use clap::{Parser, Subcommand};
+use futures_util::{stream, StreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 
-    let pdb_path = Path::new(dll_path).parent().unwrap();
-    let pdb_path = Path::join(pdb_path, pdb_name);
-    let pdb_path = pdb_path.to_str().unwrap();
+use unity_pdb_downloader::{parse_dll, SymbolDownloader, Symbolizer};
 
-    Ok(DllInfo { dll_path: dll_path.to_string(), dll_guid, pdb_name: pdb_name_without_ext, pdb_path: pdb_path.to_string(), cab_path: cab_path.to_string() })
+/// Fetch the PDB for a single DLL, driving `progress_bar` through the
+/// download and extract stages. Errors are returned as strings so that a batch
+/// run can collect them across the concurrency boundary.
+async fn fetch_one(downloader: &SymbolDownloader, dll_path: &str, progress_bar: &ProgressBar) -> Result<(), String> {
+    let dll_info = parse_dll(dll_path).map_err(|e| e.to_string())?;
+    downloader.fetch_pdb(&dll_info, progress_bar).await.map_err(|e| e.to_string())?;
+    Ok(())
 }
 
-async fn download_cab(dll_info: &DllInfo) -> Result<(), Box<dyn Error>> {
-    let cab_url = format!("http://symbolserver.unity3d.com/{}.pdb/{}1/{}.pd_",
-                          dll_info.pdb_name, dll_info.dll_guid, dll_info.pdb_name);
-    // println!("{cab_url}");
-    // if Path::new(&dll_info.cab_path).exists() {
-    //     return Err(Box::new(IOError::new(ErrorKind::AlreadyExists, "Cab file already exists")));
-    // }
-
-    let client = Client::builder()
-        // .proxy(Proxy::http("http://127.0.0.1:10809/").unwrap())
-        .build()?;
-    let resp = client.get(cab_url).send().await?;
-
-    if !resp.status().is_success() {
-        return Err(Box::new(IOError::new(ErrorKind::AddrNotAvailable, "Cab request failed")));
+/// Collect the DLLs to process. A file `input` is returned as-is; a directory
+/// `input` is walked for `*.dll`, descending into sub-directories only when
+/// `recursive` is set.
+fn collect_dlls(input: &str, recursive: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    let path = Path::new(input);
+    if path.is_file() {
+        return Ok(vec![input.to_string()]);
     }
 
-    let total_size = resp.headers()
-        .get("content-length")
-        .and_then(|x| x.to_str().ok())
-        .and_then(|x| x.parse::<u64>().ok())
-        .unwrap_or(0);
-
-    if total_size == 0 {
-        return Err(Box::new(IOError::new(ErrorKind::AddrNotAvailable, "Cab size wrong")));
+    let mut dlls = Vec::new();
+    let mut dirs = vec![path.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                if recursive {
+                    dirs.push(entry_path);
+                }
+            } else if entry_path.extension().and_then(|e| e.to_str()).is_some_and(|e| e.eq_ignore_ascii_case("dll")) {
+                if let Some(p) = entry_path.to_str() {
+                    dlls.push(p.to_string());
+                }
+            }
+        }
     }
+    dlls.sort();
+    Ok(dlls)
+}
 
-    let mut out_file = tokio::fs::File::create(&dll_info.cab_path).await?;
+/// Parse a hex address with an optional `0x` prefix.
+fn parse_hex(s: &str) -> Result<u32, String> {
+    let t = s.trim().trim_start_matches("0x").trim_start_matches("0X");
+    u32::from_str_radix(t, 16).map_err(|e| format!("invalid hex address `{s}`: {e}"))
+}
 
-    let mut stream = resp.bytes_stream();
+/// Read hex addresses, one per line, from `src` (a file path, or `-` for
+/// stdin). Blank lines are skipped.
+fn read_addresses(src: &str) -> Result<Vec<u32>, Box<dyn Error>> {
+    let content = if src == "-" {
+        let mut s = String::new();
+        std::io::stdin().read_to_string(&mut s)?;
+        s
+    } else {
+        std::fs::read_to_string(src)?
+    };
+
+    let mut addresses = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if !line.is_empty() {
+            addresses.push(parse_hex(line)?);
+        }
+    }
+    Ok(addresses)
+}
 
-    // 创建一个新的进度条
-    let progress_bar = ProgressBar::new(total_size);
-    progress_bar.set_message("Download cab file");
+#[derive(Parser, Debug)]
+#[command(name = "Unity PDB Downloader", version = "1.0.0", author = "jeferwang")]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
 
-    // 设置进度条的样式
-    progress_bar.set_style(
-        ProgressStyle::default_bar()
-            .template("{spinner:.green} {msg} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")
-            .unwrap()
-        //.progress_chars("#>-")
-    );
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Download and extract the PDB for a DLL, or batch over a directory.
+    Download(DownloadArgs),
+    /// Fetch a DLL's PDB and resolve raw addresses to symbol names.
+    Symbolize(SymbolizeArgs),
+}
 
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk?;
-        out_file.write_all(&chunk).await?;
-        progress_bar.inc(chunk.len() as u64);
-    }
+#[derive(Parser, Debug)]
+struct DownloadArgs {
+    /// Path to a single DLL, or a directory to batch over.
+    #[arg(short, long)]
+    input: String,
 
-    progress_bar.finish();
+    /// Recurse into sub-directories when `input` is a directory.
+    #[arg(short, long)]
+    recursive: bool,
 
-    Ok(())
-}
+    /// Maximum number of DLLs to process concurrently.
+    #[arg(short, long, default_value_t = 8)]
+    jobs: usize,
 
-async fn extract_cab(dll_info: &DllInfo) -> Result<(), Box<dyn Error>> {
-    let spinner = ProgressBar::new_spinner();
-    spinner.set_message("Extract cab file");
-    spinner.enable_steady_tick(Duration::from_millis(100));
-    spinner.set_style(ProgressStyle::default_spinner().template("{spinner:.green} {msg} {elapsed_precise}").unwrap());
+    /// Lay extracted PDBs out in a SymSrv-style symbol cache under this
+    /// directory, skipping downloads that are already cached.
+    #[arg(short, long)]
+    symbol_cache: Option<String>,
 
-    {
-        let cab_file = File::open(&dll_info.cab_path)?;
-        let mut cabinet = cab::Cabinet::new(cab_file)?;
+    /// Symbol server to query, repeatable. Defaults to Unity's server plus
+    /// Microsoft's when omitted.
+    #[arg(long)]
+    symbol_server: Vec<String>,
+}
 
-        let mut files: Vec<String> = vec![];
+#[derive(Parser, Debug)]
+struct SymbolizeArgs {
+    /// Path to the DLL whose PDB to fetch and symbolize against.
+    #[arg(short, long)]
+    input: String,
 
-        for folder in cabinet.folder_entries().into_iter() {
-            for file in folder.file_entries() {
-                files.push(file.name().to_string());
-            }
-        }
+    /// Lay the fetched PDB out in a SymSrv-style symbol cache under this
+    /// directory, re-using it on later runs.
+    #[arg(short, long)]
+    symbol_cache: Option<String>,
 
-        for file in files {
-            let mut reader = cabinet.read_file(file.as_str())?;
-            let mut writer = File::create(dll_info.pdb_path.as_str())?;
-            std::io::copy(&mut reader, &mut writer)?;
-        }
-    }
+    /// A single address to resolve, as hex (e.g. `0x1234`).
+    #[arg(long, value_parser = parse_hex)]
+    rva: Option<u32>,
 
-    spinner.finish();
+    /// Read addresses (one hex value per line) from this file, or `-` for
+    /// stdin.
+    #[arg(short = 'f', long)]
+    addresses: Option<String>,
 
-    Ok(())
-}
+    /// Demangle MSVC-decorated symbol names.
+    #[arg(short, long)]
+    demangle: bool,
 
-async fn delete_cab(dll_info: &DllInfo) -> Result<(), Box<dyn Error>> {
-    tokio::fs::remove_file(&dll_info.cab_path).await?;
-    Ok(())
+    /// Symbol server to query, repeatable. Defaults to Unity's server plus
+    /// Microsoft's when omitted.
+    #[arg(long)]
+    symbol_server: Vec<String>,
 }
 
-#[derive(Parser, Debug)]
-#[command(name = "Unity PDB Downloader", version = "1.0.0", author = "jeferwang")]
-struct Args {
-    #[arg(short, long)]
-    input: String,
+/// Build a downloader, honoring an explicit list of `--symbol-server` values
+/// and falling back to the default Unity + Microsoft servers when none are
+/// given.
+fn build_downloader(
+    symbol_cache: Option<String>,
+    symbol_server: Vec<String>,
+) -> unity_pdb_downloader::Result<SymbolDownloader> {
+    let cache = symbol_cache.map(Into::into);
+    if symbol_server.is_empty() {
+        SymbolDownloader::new(cache)
+    } else {
+        SymbolDownloader::with_servers(cache, symbol_server)
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     let args = Args::parse();
-    let dll_path = args.input;
 
-    println!("Input DLL file: {dll_path}");
+    match args.command {
+        Command::Download(args) => run_download(args).await,
+        Command::Symbolize(args) => run_symbolize(args).await,
+    }
+}
+
+/// Download and extract PDBs for a DLL or a directory of DLLs, rendering a
+/// `MultiProgress` and reporting per-DLL failures at the end.
+async fn run_download(args: DownloadArgs) -> Result<(), Box<dyn Error>> {
+    let dlls = collect_dlls(&args.input, args.recursive)?;
+    if dlls.is_empty() {
+        println!("No DLL files found under {}", args.input);
+        return Ok(());
+    }
+    println!("Found {} DLL file(s)", dlls.len());
+
+    let downloader = build_downloader(args.symbol_cache, args.symbol_server)?;
+
+    let multi = MultiProgress::new();
+    let overall = multi.add(ProgressBar::new(dlls.len() as u64));
+    overall.set_style(
+        ProgressStyle::default_bar()
+            .template("{msg} [{bar:40.green/white}] {pos}/{len}")
+            .unwrap(),
+    );
+    overall.set_message("Overall");
+
+    let results = stream::iter(dlls.iter().map(|dll_path| {
+        let downloader = &downloader;
+        let multi = &multi;
+        let overall = &overall;
+        async move {
+            let progress_bar = multi.add(ProgressBar::new(0));
+            let result = fetch_one(downloader, dll_path, &progress_bar).await;
+            progress_bar.finish_and_clear();
+            multi.remove(&progress_bar);
+            overall.inc(1);
+            (dll_path.clone(), result)
+        }
+    }))
+        .buffer_unordered(args.jobs)
+        .collect::<Vec<_>>()
+        .await;
+
+    overall.finish();
+
+    let failures: Vec<(String, String)> = results
+        .into_iter()
+        .filter_map(|(dll, res)| res.err().map(|e| (dll, e)))
+        .collect();
+
+    if failures.is_empty() {
+        println!("All {} PDB(s) downloaded successfully", dlls.len());
+    } else {
+        eprintln!("\n{} of {} DLL(s) failed:", failures.len(), dlls.len());
+        for (dll, err) in &failures {
+            eprintln!("  {dll}: {err}");
+        }
+    }
 
-    let dll_info = parse_dll(dll_path.as_str()).expect("parse dll failed");
-    println!("Parsed {:#?}", dll_info);
+    Ok(())
+}
+
+/// Fetch a single DLL's PDB, then resolve the requested addresses against it,
+/// printing one line each: the address as zero-padded hex, followed by its
+/// `module!symbol+0xdelta` (or `module+0xrva`) resolution.
+async fn run_symbolize(args: SymbolizeArgs) -> Result<(), Box<dyn Error>> {
+    let dll_info = parse_dll(&args.input)?;
+    let downloader = build_downloader(args.symbol_cache, args.symbol_server)?;
+
+    let progress_bar = ProgressBar::new(0);
+    downloader.fetch_pdb(&dll_info, &progress_bar).await?;
+    progress_bar.finish_and_clear();
 
-    download_cab(&dll_info).await.expect("download cab failed");
+    let pdb_path = downloader.resolved(&dll_info).pdb_path;
+    let symbolizer = Symbolizer::open(&pdb_path, &dll_info.pdb_name)?;
 
-    extract_cab(&dll_info).await.expect("extract cab failed");
+    let mut addresses = Vec::new();
+    if let Some(rva) = args.rva {
+        addresses.push(rva);
+    }
+    if let Some(src) = &args.addresses {
+        addresses.extend(read_addresses(src)?);
+    }
+    if addresses.is_empty() {
+        eprintln!("No addresses to resolve; pass --rva or --addresses");
+        return Ok(());
+    }
 
-    delete_cab(&dll_info).await.expect("delete cab failed");
+    for rva in addresses {
+        println!("{:#010x}  {}", rva, symbolizer.resolve(rva, args.demangle));
+    }
 
     Ok(())
 }